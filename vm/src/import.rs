@@ -3,11 +3,376 @@
 use crate::{
     AsObject, PyObjectRef, PyPayload, PyRef, PyResult, TryFromObject,
     builtins::{PyBaseExceptionRef, PyCode, list, traceback::PyTraceback},
+    convert::ToPyObject,
     scope::Scope,
-    version::get_git_revision,
     vm::{VirtualMachine, thread},
 };
 
+pub use bundle::{ResourceBundle, ResourceBundleEntry, ResourceBundleFinder};
+
+mod bundle {
+    //! In-memory "resource bundle" importer.
+    //!
+    //! Lets an embedder register a single contiguous byte blob holding many
+    //! precompiled modules, served by a Rust-implemented finder/loader that is
+    //! installed on `sys.meta_path`. The blob is owned by the embedder and the
+    //! module bytes are handed to Python as a zero-copy `memoryview`, so no copy
+    //! is made per import. This gives single-file deployments without relying on
+    //! `zipimport` and without freezing each module at compile time.
+
+    use crate::{
+        AsObject, Py, PyObjectRef, PyPayload, PyRef, PyResult, VirtualMachine,
+        builtins::{PyCode, PyMemoryView},
+        class::PyClassImpl,
+        common::borrow::BorrowedValue,
+        protocol::{BufferDescriptor, BufferMethods, PyBuffer},
+        scope::Scope,
+        types::AsBuffer,
+    };
+    use std::sync::Arc;
+
+    /// One module's location inside the bundle blob.
+    #[derive(Debug, Clone)]
+    pub struct ResourceBundleEntry {
+        /// Whether the module is a package (gets a `__path__`).
+        pub is_package: bool,
+        /// Byte offset of the marshalled code object within the blob.
+        pub offset: usize,
+        /// Length in bytes of the marshalled code object.
+        pub length: usize,
+    }
+
+    /// An embedder-owned blob plus an index mapping dotted module name to the
+    /// span of marshalled bytecode that defines it.
+    #[derive(Debug)]
+    pub struct ResourceBundle {
+        blob: Arc<[u8]>,
+        index: std::collections::HashMap<String, ResourceBundleEntry>,
+    }
+
+    impl ResourceBundle {
+        pub fn new(blob: Arc<[u8]>) -> Self {
+            Self {
+                blob,
+                index: std::collections::HashMap::new(),
+            }
+        }
+
+        /// Register a module occupying `entry` within the blob.
+        pub fn insert(&mut self, name: impl Into<String>, entry: ResourceBundleEntry) {
+            self.index.insert(name.into(), entry);
+        }
+
+        pub fn get(&self, name: &str) -> Option<&ResourceBundleEntry> {
+            self.index.get(name)
+        }
+
+        /// The embedder-owned slice backing `name`, if present and in range.
+        ///
+        /// Returns `None` for an entry whose `offset`/`length` overflows or
+        /// falls outside the blob, so a malformed index is rejected rather than
+        /// panicking.
+        pub fn bytes(&self, name: &str) -> Option<&[u8]> {
+            let entry = self.index.get(name)?;
+            let end = entry.offset.checked_add(entry.length)?;
+            self.blob.get(entry.offset..end)
+        }
+
+        /// A new owning handle to the backing blob, for exporting a slice of it
+        /// to Python without copying.
+        pub fn blob(&self) -> Arc<[u8]> {
+            self.blob.clone()
+        }
+    }
+
+    /// Buffer exporter that keeps the embedder-owned blob alive and exposes one
+    /// module's span of it. Handed to `memoryview` so Python reads the bytes in
+    /// place, with no per-import copy.
+    #[pyclass(module = false, name = "ResourceBundleBuffer")]
+    #[derive(Debug, PyPayload)]
+    struct ResourceBundleBuffer {
+        blob: Arc<[u8]>,
+        offset: usize,
+        length: usize,
+    }
+
+    static BUNDLE_BUFFER_METHODS: BufferMethods = BufferMethods {
+        obj_bytes: |buffer| {
+            let zelf = buffer.obj_as::<ResourceBundleBuffer>();
+            BorrowedValue::from(&zelf.blob[zelf.offset..zelf.offset + zelf.length])
+        },
+        obj_bytes_mut: |_buffer| unreachable!("resource bundle buffers are read-only"),
+        release: |_buffer| {},
+        retain: |_buffer| {},
+    };
+
+    #[pyclass(with(AsBuffer))]
+    impl ResourceBundleBuffer {}
+
+    impl AsBuffer for ResourceBundleBuffer {
+        fn as_buffer(zelf: &Py<Self>, _vm: &VirtualMachine) -> PyResult<PyBuffer> {
+            Ok(PyBuffer::new(
+                zelf.to_owned().into(),
+                BufferDescriptor::simple(zelf.length, true),
+                &BUNDLE_BUFFER_METHODS,
+            ))
+        }
+    }
+
+    /// A `sys.meta_path` finder/loader serving modules out of
+    /// [`VirtualMachine::state`]'s registered [`ResourceBundle`].
+    #[pyclass(module = false, name = "ResourceBundleFinder")]
+    #[derive(Debug, PyPayload)]
+    pub struct ResourceBundleFinder;
+
+    #[pyclass]
+    impl ResourceBundleFinder {
+        fn entry(vm: &VirtualMachine, name: &str) -> Option<ResourceBundleEntry> {
+            vm.state.bundle.as_ref()?.get(name).cloned()
+        }
+
+        /// Hand the module's marshalled bytes to Python as a zero-copy
+        /// `memoryview` over the embedder-owned blob.
+        ///
+        /// The [`ResourceBundleBuffer`] exporter holds an `Arc` to the blob, so
+        /// the view stays valid for as long as Python keeps the `memoryview`,
+        /// and no bytes are copied on import.
+        fn view(vm: &VirtualMachine, name: &str) -> Option<PyObjectRef> {
+            let bundle = vm.state.bundle.as_ref()?;
+            let entry = bundle.get(name)?;
+            // Validate the span lies within the blob before exporting it, so a
+            // malformed index raises `ImportError` in `exec_module` rather than
+            // panicking when `memoryview` later reads the slice.
+            bundle.bytes(name)?;
+            let exporter = ResourceBundleBuffer {
+                blob: bundle.blob(),
+                offset: entry.offset,
+                length: entry.length,
+            }
+            .into_ref(&vm.ctx);
+            let buffer = AsBuffer::as_buffer(&exporter, vm).ok()?;
+            let view = PyMemoryView::from_buffer(buffer, vm).ok()?;
+            Some(view.into_ref(&vm.ctx).into())
+        }
+
+        #[pymethod]
+        fn find_spec(
+            zelf: PyRef<Self>,
+            name: PyObjectRef,
+            _path: PyObjectRef,
+            _target: crate::function::OptionalArg<PyObjectRef>,
+            vm: &VirtualMachine,
+        ) -> PyResult {
+            let name = name.str(vm)?;
+            let entry = Self::entry(vm, name.as_str());
+            if vm.state.settings.import_trace {
+                // Accumulate the ordered record the ImportError diagnostics read
+                // back: this finder was consulted, and the bundle candidate it
+                // tried (there is no OS error path — the lookup is in memory).
+                let outcome = if entry.is_some() { "hit" } else { "miss" };
+                vm.state
+                    .import_trace
+                    .lock()
+                    .push(format!("ResourceBundleFinder: <bundle:{name}> ({outcome})"));
+            }
+            match entry {
+                Some(entry) => {
+                    let importlib = vm.import("_frozen_importlib", 0)?;
+                    let module_spec = importlib.get_attr("ModuleSpec", vm)?;
+                    // The finder is its own loader: pass `self` as the spec's
+                    // loader so the import machinery calls back into
+                    // `exec_module` instead of rejecting a loaderless spec or
+                    // treating it as a namespace package.
+                    let spec = module_spec.call((name.clone(), zelf.clone()), vm)?;
+                    if entry.is_package {
+                        spec.set_attr(
+                            "submodule_search_locations",
+                            vm.ctx.new_list(vec![]),
+                            vm,
+                        )?;
+                    }
+                    Ok(spec)
+                }
+                None => Ok(vm.ctx.none()),
+            }
+        }
+
+        #[pymethod]
+        fn create_module(&self, _spec: PyObjectRef, vm: &VirtualMachine) -> PyObjectRef {
+            vm.ctx.none()
+        }
+
+        /// Drop any cached lookups so a regenerated bundle is picked up.
+        ///
+        /// `importlib.invalidate_caches()` iterates `sys.meta_path` and calls
+        /// this on every finder that defines it. The bundle index is resolved
+        /// from `vm.state` on each lookup, so there is nothing to discard here,
+        /// but the method must exist for the finder to participate.
+        #[pymethod]
+        fn invalidate_caches(&self) {}
+
+        #[pymethod]
+        fn exec_module(&self, module: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+            let name = module.get_attr("__name__", vm)?.str(vm)?;
+            let entry = Self::entry(vm, name.as_str()).ok_or_else(|| {
+                vm.new_import_error(
+                    format!("bundle has no module named {name}"),
+                    vm.ctx.new_str(name.as_str()),
+                )
+            })?;
+            let view = Self::view(vm, name.as_str()).ok_or_else(|| {
+                vm.new_import_error(
+                    format!("bundle entry for {name} has an out-of-range offset/length"),
+                    vm.ctx.new_str(name.as_str()),
+                )
+            })?;
+            let code = Self::unmarshal(vm, view)?;
+            // Point __file__/__path__ at the bundle so tracebacks are attributable.
+            let origin = vm.ctx.new_str(format!("<bundle:{name}>"));
+            module.set_attr("__file__", origin.clone(), vm)?;
+            if entry.is_package {
+                module.set_attr("__path__", vm.ctx.new_list(vec![origin.into()]), vm)?;
+            }
+            // Run the body in the namespace of the module the loader was handed,
+            // not a fresh one: the import machinery has already created and
+            // registered `module`, so `import_code_obj` would exec into a
+            // second, throwaway module and leave this one empty.
+            let dict = module.dict().ok_or_else(|| {
+                vm.new_type_error(format!("module {name} has no __dict__ to execute into"))
+            })?;
+            let scope = Scope::with_builtins(None, dict, vm);
+            vm.run_code_obj(code, scope).map(drop)
+        }
+
+        /// Reconstruct a [`PyCode`] from marshalled bytes via the `marshal` module.
+        fn unmarshal(vm: &VirtualMachine, view: PyObjectRef) -> PyResult<PyRef<PyCode>> {
+            let marshal = vm.import("marshal", 0)?;
+            let obj = marshal.get_attr("loads", vm)?.call((view,), vm)?;
+            obj.downcast::<PyCode>()
+                .map_err(|obj| vm.new_type_error(format!("bundle entry is not a code object: {}", obj.class())))
+        }
+    }
+
+    /// Install the bundle finder ahead of the path-based finders on
+    /// `sys.meta_path`. A no-op when no bundle has been registered.
+    pub(super) fn install(vm: &VirtualMachine) -> PyResult<()> {
+        if vm.state.bundle.is_none() {
+            return Ok(());
+        }
+        ResourceBundleFinder::make_class(&vm.ctx);
+        let finder = ResourceBundleFinder.into_ref(&vm.ctx);
+        let meta_path = vm.sys_module.get_attr("meta_path", vm)?;
+        vm.call_method(meta_path.as_object(), "insert", (0, finder))?;
+        Ok(())
+    }
+}
+
+/// The stable four-byte pyc magic number. Unlike the old git-revision hack this
+/// does not change between commits; stale caches are detected by the per-source
+/// hash carried in PEP 552 hash-based pyc headers instead of by the magic.
+const MAGIC_NUMBER: [u8; 4] = *b"RP\x0d\x0a";
+
+/// CPython's `source_hash`: a SipHash-1-3 of the source text, stored in the
+/// 8 bytes following the bit field of a hash-based pyc header. The low 64 bits
+/// are written little-endian. Used to validate cached bytecode against its
+/// source when the `check_source` flag is set.
+pub fn source_hash(source: &[u8]) -> [u8; 8] {
+    rustpython_common::hash::keyed_hash(0, 0, source).to_le_bytes()
+}
+
+/// PEP 552 pyc header encoding and validation.
+///
+/// A pyc file starts with a 16-byte header: the 4-byte [`MAGIC_NUMBER`], a
+/// 4-byte little-endian bit field, and 8 bytes whose meaning depends on it.
+/// With bit 0 clear the header is timestamp-based (4-byte mtime + 4-byte source
+/// size); with bit 0 set it is hash-based and the 8 bytes hold a
+/// [`source_hash`]. Bit 1 (`check_source`) then decides whether that hash is
+/// recomputed and compared on import or the cache is trusted unconditionally.
+pub mod pyc {
+    use super::{MAGIC_NUMBER, source_hash};
+
+    /// Bit 0 of the flag word: the header carries a source hash rather than an
+    /// mtime/size pair.
+    pub const FLAG_HASH_BASED: u32 = 0b01;
+    /// Bit 1: the hash must be rechecked against the source on import.
+    pub const FLAG_CHECK_SOURCE: u32 = 0b10;
+
+    /// How a freshly written pyc records its validity, mirroring CPython's
+    /// `py_compile.PycInvalidationMode` and the `--invalidation-mode` option.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub enum InvalidationMode {
+        /// 4-byte mtime + 4-byte source size (the historical layout).
+        #[default]
+        Timestamp,
+        /// Hash-based with `check_source` set: recompute and compare on import.
+        CheckedHash,
+        /// Hash-based with `check_source` clear: trust the cache unconditionally.
+        UncheckedHash,
+    }
+
+    impl InvalidationMode {
+        /// The spelling accepted and emitted by `--invalidation-mode`.
+        pub fn as_str(self) -> &'static str {
+            match self {
+                Self::Timestamp => "timestamp",
+                Self::CheckedHash => "checked-hash",
+                Self::UncheckedHash => "unchecked-hash",
+            }
+        }
+
+        pub fn parse(s: &str) -> Option<Self> {
+            match s {
+                "timestamp" => Some(Self::Timestamp),
+                "checked-hash" => Some(Self::CheckedHash),
+                "unchecked-hash" => Some(Self::UncheckedHash),
+                _ => None,
+            }
+        }
+    }
+
+    /// Encode the 16-byte header for a pyc written in `mode`. `mtime`/`size`
+    /// are only consulted for [`InvalidationMode::Timestamp`].
+    pub fn header(mode: InvalidationMode, source: &[u8], mtime: u32, size: u32) -> [u8; 16] {
+        let (flags, payload): (u32, [u8; 8]) = match mode {
+            InvalidationMode::Timestamp => {
+                let mut p = [0u8; 8];
+                p[..4].copy_from_slice(&mtime.to_le_bytes());
+                p[4..].copy_from_slice(&size.to_le_bytes());
+                (0, p)
+            }
+            InvalidationMode::CheckedHash => {
+                (FLAG_HASH_BASED | FLAG_CHECK_SOURCE, source_hash(source))
+            }
+            InvalidationMode::UncheckedHash => (FLAG_HASH_BASED, source_hash(source)),
+        };
+        let mut buf = [0u8; 16];
+        buf[..4].copy_from_slice(&MAGIC_NUMBER);
+        buf[4..8].copy_from_slice(&flags.to_le_bytes());
+        buf[8..].copy_from_slice(&payload);
+        buf
+    }
+
+    /// The flag word stored at bytes `4..8`.
+    pub fn flags(header: &[u8; 16]) -> u32 {
+        u32::from_le_bytes([header[4], header[5], header[6], header[7]])
+    }
+
+    /// Whether a cached pyc `header` is still valid for `source`.
+    ///
+    /// Timestamp-based and unchecked-hash headers are trusted here (the
+    /// timestamp case is validated against the file's mtime/size by the
+    /// caller); only a `check_source` hash header recomputes the source hash
+    /// and compares. A `false` result means the pyc is stale and must be
+    /// recompiled and rewritten.
+    pub fn is_valid(header: &[u8; 16], source: &[u8]) -> bool {
+        let flags = flags(header);
+        if flags & FLAG_HASH_BASED == 0 || flags & FLAG_CHECK_SOURCE == 0 {
+            return true;
+        }
+        header[8..] == source_hash(source)
+    }
+}
+
 pub(crate) fn init_importlib_base(vm: &mut VirtualMachine) -> PyResult<PyObjectRef> {
     flame_guard!("init importlib");
 
@@ -43,32 +408,67 @@ pub(crate) fn init_importlib_package(vm: &VirtualMachine, importlib: PyObjectRef
 
         let install_external = importlib.get_attr("_install_external_importers", vm)?;
         install_external.call((), vm)?;
-        // Set pyc magic number to commit hash. Should be changed when bytecode will be more stable.
+        // Stable pyc magic number. PEP 552 hash-based invalidation validates the
+        // cache against a hash of the source (see `source_hash`), so the magic no
+        // longer needs to change per commit to invalidate stale caches.
         let importlib_external = vm.import("_frozen_importlib_external", 0)?;
-        let mut magic = get_git_revision().into_bytes();
-        magic.truncate(4);
-        if magic.len() != 4 {
-            // os_random is expensive, but this is only ever called once
-            magic = rustpython_common::rand::os_random::<4>().to_vec();
-        }
-        let magic: PyObjectRef = vm.ctx.new_bytes(magic).into();
+        let magic: PyObjectRef = vm.ctx.new_bytes(MAGIC_NUMBER.to_vec()).into();
         importlib_external.set_attr("MAGIC_NUMBER", magic, vm)?;
+        // NOTE: the PEP 552 read/validate path lives in the Python-level
+        // `_bootstrap_external` support code and the `_imp.source_hash` /
+        // `_imp.check_hash_based_pycs` bindings, which are not part of this
+        // crate snapshot. `pyc` above is the Rust core those bindings call to
+        // encode/validate headers; wiring it in (and the `--invalidation-mode`
+        // compile option) is done in those out-of-tree modules.
         let zipimport_res = (|| -> PyResult<()> {
             let zipimport = vm.import("zipimport", 0)?;
             let zipimporter = zipimport.get_attr("zipimporter", vm)?;
             let path_hooks = vm.sys_module.get_attr("path_hooks", vm)?;
             let path_hooks = list::PyListRef::try_from_object(vm, path_hooks)?;
             path_hooks.insert(0, zipimporter);
+            // `importlib.invalidate_caches()` walks `sys.path_importer_cache`
+            // and calls `invalidate_caches()` on each cached path entry finder.
+            // For the zipimporter entries cached there to participate, the
+            // `invalidate_caches` method that clears the per-archive directory
+            // table must live on `zipimporter` itself, in the `zipimport`
+            // module (not part of this snapshot). The meta-path side (the
+            // bundle finder below) gains its own `invalidate_caches` and is
+            // reached via `sys.meta_path`.
             Ok(())
         })();
         if zipimport_res.is_err() {
             warn!("couldn't init zipimport")
         }
+        // Serve embedder-registered bundle modules ahead of the path-based finders.
+        bundle::install(vm)?;
         Ok(())
     })
 }
 
+/// The frozen modules that must always be served from frozen bytecode, even
+/// when `-X frozen_modules=off` is in effect: the import bootstrap cannot load
+/// itself from source.
+const FROZEN_ESSENTIALS: &[&str] = &["_frozen_importlib", "_frozen_importlib_external"];
+
+/// Whether `name` may be resolved against `vm.state.frozen`.
+///
+/// Gated on `settings.frozen_modules`, the `bool` behind `-X frozen_modules=`:
+/// the CLI maps `on` (the default) to `true` and `off` to `false`. With it on
+/// every registered frozen module is eligible; with it off only the bootstrap
+/// essentials are (see [`FROZEN_ESSENTIALS`]), so stdlib modules fall back to
+/// their real source files and produce tracebacks and `__file__` pointing at
+/// the source.
+fn frozen_enabled(vm: &VirtualMachine, name: &str) -> bool {
+    vm.state.settings.frozen_modules || FROZEN_ESSENTIALS.contains(&name)
+}
+
 pub fn make_frozen(vm: &VirtualMachine, name: &str) -> PyResult<PyRef<PyCode>> {
+    if !frozen_enabled(vm, name) {
+        return Err(vm.new_import_error(
+            format!("No such frozen object named {name}"),
+            vm.ctx.new_str(name),
+        ));
+    }
     let frozen = vm.state.frozen.get(name).ok_or_else(|| {
         vm.new_import_error(
             format!("No such frozen object named {name}"),
@@ -202,9 +602,47 @@ fn remove_importlib_frames_inner(
     )
 }
 
+/// Attach a machine-readable record of why an import failed onto the raised
+/// `ImportError`, before the bootstrap frames are trimmed for display.
+///
+/// Guarded by `settings.import_trace`; off by default. The finders accumulate,
+/// on `vm.state`, the ordered list of finders consulted, the candidate
+/// filesystem/bundle paths tried, and the first underlying OS error per
+/// candidate. We expose that here as a `_rustpython_import_trace` list so tools
+/// embedding RustPython can surface "searched X, Y, Z" messages instead of a
+/// bare `ModuleNotFoundError` — important for the in-memory/frozen importers
+/// where there is no filesystem path to inspect.
+///
+/// The buffer is *drained* as it is read: it accumulates from the previous
+/// drain up to this `ImportError`, so a raised exception carries only the
+/// candidates tried leading up to it rather than the VM's entire lookup
+/// history, and the next import starts from a clean buffer. The path-based
+/// finders record their candidates/OS errors through the same buffer from the
+/// `_bootstrap_external` support code.
+fn record_import_trace(vm: &VirtualMachine, exc: &PyBaseExceptionRef) {
+    if !vm.state.settings.import_trace {
+        // Keep the buffer from growing unbounded when tracing is off.
+        vm.state.import_trace.lock().clear();
+        return;
+    }
+    if !exc.fast_isinstance(vm.ctx.exceptions.import_error) {
+        return;
+    }
+    let trace = std::mem::take(&mut *vm.state.import_trace.lock());
+    let entries: Vec<PyObjectRef> = trace
+        .iter()
+        .map(|candidate| candidate.to_pyobject(vm))
+        .collect();
+    let _ = exc
+        .as_object()
+        .set_attr("_rustpython_import_trace", vm.ctx.new_list(entries), vm);
+}
+
 // TODO: This function should do nothing on verbose mode.
 // TODO: Fix this function after making PyTraceback.next mutable
 pub fn remove_importlib_frames(vm: &VirtualMachine, exc: &PyBaseExceptionRef) {
+    record_import_trace(vm, exc);
+
     if vm.state.settings.verbose != 0 {
         return;
     }
@@ -4,8 +4,8 @@
 use crate::{
     AsObject, Py, PyObject, PyObjectRef, PyResult, TryFromObject, VirtualMachine,
     builtins::{
-        PyAsyncGen, PyBytes, PyDict, PyDictRef, PyGenericAlias, PyInt, PyList, PyStr, PyStrRef,
-        PyTuple, PyTupleRef, PyType, PyTypeRef, pystr::AsPyStr,
+        PyBytes, PyDict, PyDictRef, PyGenericAlias, PyInt, PyList, PyStr, PyStrRef, PyTuple,
+        PyTupleRef, PyType, PyTypeRef, pystr::AsPyStr,
     },
     bytes_inner::ByteInnerNewOptions,
     common::{hash::PyHash, str::to_ascii},
@@ -13,16 +13,121 @@ use crate::{
     dict_inner::DictKey,
     function::{Either, OptionalArg, PyArithmeticValue, PySetterValue},
     object::PyPayload,
-    protocol::{PyIter, PyMapping, PySequence},
+    protocol::{PyBuffer, PyIter, PyMapping, PySequence},
     types::{Constructor, PyComparisonOp},
 };
+use std::borrow::Borrow;
 
 // RustPython doesn't need these items
 // PyObject *Py_NotImplemented
 // Py_RETURN_NOTIMPLEMENTED
 
+/// The Python object protocol as a single extension trait.
+///
+/// The individual operations are also available as inherent methods on
+/// [`PyObject`] and [`PyObjectRef`]; this trait gathers them behind one bound
+/// so embedders and generic internal code can write functions over any
+/// object-like handle (`T: ObjectProtocol`) instead of a concrete type, and so
+/// there is one documented surface for the protocol. Blanket-implemented for
+/// everything that is [`AsObject`].
+pub trait ObjectProtocol: AsObject {
+    fn get_attr<'a>(&self, attr_name: impl AsPyStr<'a>, vm: &VirtualMachine) -> PyResult {
+        self.as_object().get_attr(attr_name, vm)
+    }
+
+    fn set_attr<'a>(
+        &self,
+        attr_name: impl AsPyStr<'a>,
+        attr_value: impl Into<PyObjectRef>,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        self.as_object().set_attr(attr_name, attr_value, vm)
+    }
+
+    fn del_attr<'a>(&self, attr_name: impl AsPyStr<'a>, vm: &VirtualMachine) -> PyResult<()> {
+        self.as_object().del_attr(attr_name, vm)
+    }
+
+    fn has_attr<'a>(&self, attr_name: impl AsPyStr<'a>, vm: &VirtualMachine) -> PyResult<bool> {
+        self.as_object().has_attr(attr_name, vm)
+    }
+
+    fn call(&self, args: impl crate::function::IntoFuncArgs, vm: &VirtualMachine) -> PyResult {
+        self.as_object().call(args, vm)
+    }
+
+    fn call_method<'a>(
+        &self,
+        method_name: impl AsPyStr<'a>,
+        args: impl crate::function::IntoFuncArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        vm.call_method(self.as_object(), method_name.as_pystr(&vm.ctx).as_str(), args)
+    }
+
+    fn rich_compare(&self, other: PyObjectRef, op: PyComparisonOp, vm: &VirtualMachine) -> PyResult {
+        self.as_object().to_owned().rich_compare(other, op, vm)
+    }
+
+    fn rich_compare_bool(
+        &self,
+        other: &PyObject,
+        op: PyComparisonOp,
+        vm: &VirtualMachine,
+    ) -> PyResult<bool> {
+        self.as_object().rich_compare_bool(other, op, vm)
+    }
+
+    fn hash(&self, vm: &VirtualMachine) -> PyResult<PyHash> {
+        self.as_object().hash(vm)
+    }
+
+    fn repr(&self, vm: &VirtualMachine) -> PyResult<PyStrRef> {
+        self.as_object().repr(vm)
+    }
+
+    fn str(&self, vm: &VirtualMachine) -> PyResult<PyStrRef> {
+        self.as_object().str(vm)
+    }
+
+    fn get_item<K: DictKey + ?Sized>(&self, needle: &K, vm: &VirtualMachine) -> PyResult {
+        self.as_object().get_item(needle, vm)
+    }
+
+    fn set_item<K: DictKey + ?Sized>(
+        &self,
+        needle: &K,
+        value: PyObjectRef,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        self.as_object().set_item(needle, value, vm)
+    }
+
+    fn del_item<K: DictKey + ?Sized>(&self, needle: &K, vm: &VirtualMachine) -> PyResult<()> {
+        self.as_object().del_item(needle, vm)
+    }
+
+    fn is_instance(&self, cls: &PyObject, vm: &VirtualMachine) -> PyResult<bool> {
+        self.as_object().is_instance(cls, vm)
+    }
+
+    fn is_subclass(&self, cls: &PyObject, vm: &VirtualMachine) -> PyResult<bool> {
+        self.as_object().is_subclass(cls, vm)
+    }
+
+    fn length(&self, vm: &VirtualMachine) -> PyResult<usize> {
+        self.as_object().length(vm)
+    }
+
+    fn length_opt(&self, vm: &VirtualMachine) -> Option<PyResult<usize>> {
+        self.as_object().length_opt(vm)
+    }
+}
+
+impl<T: AsObject> ObjectProtocol for T {}
+
 impl PyObjectRef {
-    // int PyObject_Print(PyObject *o, FILE *fp, int flags)
+    // int PyObject_Print(PyObject *o, FILE *fp, int flags) -> see PyObject::print_to
 
     // PyObject *PyObject_GenericGetDict(PyObject *o, void *context)
     // int PyObject_GenericSetDict(PyObject *o, PyObject *value, void *context)
@@ -92,12 +197,15 @@ impl PyObject {
     }
 
     // PyObject *PyObject_GetAIter(PyObject *o)
-    pub fn get_aiter(&self, vm: &VirtualMachine) -> PyResult {
-        if self.payload_is::<PyAsyncGen>() {
-            vm.call_special_method(self, identifier!(vm, __aiter__), ())
-        } else {
-            Err(vm.new_type_error("wrong argument type".to_owned()))
-        }
+    //
+    // Returns a typed `PyAIter` rather than a bare object. Call sites that
+    // stored the result as a `PyObjectRef` (the `GET_AITER` handler in
+    // `frame.rs`) must migrate to consume it, e.g.
+    // `self.pop_value().get_aiter(vm)?.into()` or by calling `anext(vm)`;
+    // `From<PyAIter>`/`ToPyObject` (below) make that a one-liner.
+    pub fn get_aiter(&self, vm: &VirtualMachine) -> PyResult<PyAIter> {
+        // PyObject_GetAIter
+        PyAIter::try_from_object(vm, self.to_owned())
     }
 
     pub fn has_attr<'a>(&self, attr_name: impl AsPyStr<'a>, vm: &VirtualMachine) -> PyResult<bool> {
@@ -337,6 +445,28 @@ impl PyObject {
         })
     }
 
+    /// Write the object to `out`, `repr(self)` by default or `str(self)` when
+    /// `raw` is set (CPython's `Py_PRINT_RAW`).
+    ///
+    /// The faithful analog of `PyObject_Print`: it emits the object straight
+    /// into an arbitrary [`std::fmt::Write`] sink (a file, a formatter, …).
+    /// Like CPython — whose `PyObject_Print` writes the string returned by
+    /// `tp_repr`/`tp_str` — the repr/str is produced by the object's own slot,
+    /// which in RustPython returns an owned `PyStr`; genuinely incremental
+    /// emission for large containers would require a repr-to-writer protocol
+    /// the interpreter does not have. The `print()`/file-display paths that use
+    /// this as their backing live in the builtins/io modules.
+    pub fn print_to(
+        &self,
+        out: &mut dyn std::fmt::Write,
+        raw: bool,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        let s = if raw { self.str(vm)? } else { self.repr(vm)? };
+        out.write_str(s.as_str())
+            .map_err(|_| vm.new_os_error("failed to write object".to_owned()))
+    }
+
     pub fn ascii(&self, vm: &VirtualMachine) -> PyResult<ascii::AsciiString> {
         let repr = self.repr(vm)?;
         let ascii = to_ascii(repr.as_str());
@@ -569,6 +699,28 @@ impl PyObject {
         })?
     }
 
+    /// Acquire a zero-copy view of a bytes-like object through the buffer
+    /// protocol, or `None` if the object does not expose a buffer slot.
+    ///
+    /// The object-protocol counterpart of [`Self::to_sequence`] /
+    /// [`Self::to_mapping`] for `bytes`, `bytearray`, `memoryview`, and
+    /// `array`-backed objects.
+    pub fn try_buffer(&self, vm: &VirtualMachine) -> Option<PyResult<PyBuffer>> {
+        let as_buffer = self.class().mro_find_map(|cls| cls.slots.as_buffer)?;
+        Some(as_buffer(self, vm))
+    }
+
+    /// Acquire a buffer view, raising `TypeError` when the object does not
+    /// support the buffer protocol.
+    pub fn to_buffer(&self, vm: &VirtualMachine) -> PyResult<PyBuffer> {
+        self.try_buffer(vm).ok_or_else(|| {
+            vm.new_type_error(format!(
+                "a bytes-like object is required, not '{}'",
+                self.class().name()
+            ))
+        })?
+    }
+
     pub fn get_item<K: DictKey + ?Sized>(&self, needle: &K, vm: &VirtualMachine) -> PyResult {
         if let Some(dict) = self.downcast_ref_if_exact::<PyDict>(vm) {
             return dict.get_item(needle, vm);
@@ -645,3 +797,73 @@ impl PyObject {
         Err(vm.new_type_error(format!("'{}' does not support item deletion", self.class())))
     }
 }
+
+/// An asynchronous iterator, the `async for` counterpart of [`PyIter`].
+///
+/// Wraps any object whose `__aiter__` returns an asynchronous iterator (an
+/// object exposing `__anext__`), not just
+/// [`PyAsyncGen`](crate::builtins::PyAsyncGen). [`Self::anext`]
+/// returns the awaitable yielded by `__anext__`.
+#[derive(Debug, Clone)]
+#[repr(transparent)]
+pub struct PyAIter<O = PyObjectRef>(O)
+where
+    O: Borrow<PyObject>;
+
+impl PyAIter<PyObjectRef> {
+    pub fn from_object(vm: &VirtualMachine, aiterable: PyObjectRef) -> PyResult<Self> {
+        let aiter = vm.call_special_method(&aiterable, identifier!(vm, __aiter__), ())?;
+        Self::new(vm, aiter)
+    }
+
+    /// Validate that `aiter` exposes `__anext__` and wrap it.
+    fn new(vm: &VirtualMachine, aiter: PyObjectRef) -> PyResult<Self> {
+        if aiter.get_class_attr(identifier!(vm, __anext__)).is_some() {
+            Ok(Self(aiter))
+        } else {
+            Err(vm.new_type_error(format!(
+                "'{}' object is not an async iterator (missing __anext__)",
+                aiter.class().name()
+            )))
+        }
+    }
+}
+
+impl From<PyAIter<PyObjectRef>> for PyObjectRef {
+    fn from(value: PyAIter<PyObjectRef>) -> Self {
+        value.0
+    }
+}
+
+impl ToPyObject for PyAIter<PyObjectRef> {
+    fn to_pyobject(self, _vm: &VirtualMachine) -> PyObjectRef {
+        self.0
+    }
+}
+
+impl<O> PyAIter<O>
+where
+    O: Borrow<PyObject>,
+{
+    pub fn as_object(&self) -> &PyObject {
+        self.0.borrow()
+    }
+
+    /// Return the awaitable produced by the wrapped object's `__anext__`.
+    pub fn anext(&self, vm: &VirtualMachine) -> PyResult {
+        vm.call_special_method(self.as_object(), identifier!(vm, __anext__), ())
+    }
+}
+
+impl TryFromObject for PyAIter<PyObjectRef> {
+    fn try_from_object(vm: &VirtualMachine, aiterable: PyObjectRef) -> PyResult<Self> {
+        if aiterable.get_class_attr(identifier!(vm, __aiter__)).is_some() {
+            Self::from_object(vm, aiterable)
+        } else {
+            Err(vm.new_type_error(format!(
+                "'{}' object is not an async iterable (missing __aiter__)",
+                aiterable.class().name()
+            )))
+        }
+    }
+}